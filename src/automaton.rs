@@ -0,0 +1,307 @@
+use std::collections::{HashMap, HashSet};
+
+/// The shape of closure to wrap an automaton in: `*`, `+`, or `?`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClosureKind {
+    ZeroOrMore,
+    OneOrMore,
+    ZeroOrOne,
+}
+
+/// A nondeterministic finite automaton (NFA) with epsilon transitions.
+///
+/// States are plain `usize` indices; a transition with `None` as its symbol
+/// is an epsilon (empty) move.
+#[derive(Debug, Clone)]
+pub struct Automaton {
+    pub(crate) states: usize,
+    pub(crate) start_state: usize,
+    pub(crate) accepting_states: HashSet<usize>,
+    pub(crate) transitions: HashMap<(usize, Option<char>), HashSet<usize>>,
+}
+
+impl Automaton {
+    pub fn new() -> Self {
+        Self {
+            states: 0,
+            start_state: 0,
+            accepting_states: HashSet::new(),
+            transitions: HashMap::new(),
+        }
+    }
+
+    /// Builds the automaton that matches the empty string: a single state
+    /// that is both start and accepting.
+    pub fn epsilon() -> Self {
+        let mut automaton = Automaton::new();
+        let state = automaton.add_state();
+        automaton.set_start_state(state);
+        automaton.set_accepting(state, true);
+        automaton
+    }
+
+    /// Builds the automaton that matches a single `symbol`.
+    pub fn literal(symbol: char) -> Self {
+        let mut automaton = Automaton::new();
+        let start_state = automaton.add_state();
+        let end_state = automaton.add_state();
+        automaton.set_start_state(start_state);
+        automaton.set_accepting(end_state, true);
+        automaton.add_transition(start_state, end_state, Some(symbol));
+        automaton
+    }
+
+    /// Adds a new state and returns its index.
+    pub fn add_state(&mut self) -> usize {
+        let state = self.states;
+        self.states += 1;
+        state
+    }
+
+    pub fn set_start_state(&mut self, state: usize) {
+        self.start_state = state;
+    }
+
+    pub fn set_accepting(&mut self, state: usize, accepting: bool) {
+        if accepting {
+            self.accepting_states.insert(state);
+        } else {
+            self.accepting_states.remove(&state);
+        }
+    }
+
+    pub fn clear_accepting(&mut self) {
+        self.accepting_states.clear();
+    }
+
+    pub fn add_transition(&mut self, from: usize, to: usize, symbol: Option<char>) {
+        self.transitions
+            .entry((from, symbol))
+            .or_default()
+            .insert(to);
+    }
+
+    /// Appends `other`'s states and transitions to `self`, offsetting every
+    /// state index from `other` by `self`'s state count at the time of the
+    /// call. The caller is responsible for wiring up `self`'s start and
+    /// accepting states afterwards, since merging alone doesn't imply any
+    /// particular combination semantics (concatenation, union, ...).
+    pub fn add_states_and_transitions(&mut self, other: Automaton) {
+        let offset = self.states;
+        self.states += other.states;
+        for ((from, symbol), tos) in other.transitions {
+            for to in tos {
+                self.add_transition(from + offset, to + offset, symbol);
+            }
+        }
+    }
+
+    /// Builds an automaton simulating a regular expression like ```ab```:
+    /// epsilon-links `self`'s accepting state to `other`'s start state.
+    pub fn concat(mut self, other: Automaton) -> Automaton {
+        assert_eq!(self.accepting_states.len(), 1);
+        assert_eq!(other.accepting_states.len(), 1);
+
+        let self_end = *self.accepting_states.iter().next().unwrap();
+        let other_offset = self.states;
+        let other_start = other.start_state;
+        let other_end = *other.accepting_states.iter().next().unwrap();
+
+        self.add_states_and_transitions(other);
+        self.add_transition(self_end, other_start + other_offset, None);
+        self.clear_accepting();
+        self.set_accepting(other_end + other_offset, true);
+        self
+    }
+
+    /// Builds an automaton simulating a regular expression like ```a|b```:
+    /// a new start/end pair with epsilon fan-in/fan-out to both alternatives.
+    pub fn union(self, other: Automaton) -> Automaton {
+        assert_eq!(self.accepting_states.len(), 1);
+        assert_eq!(other.accepting_states.len(), 1);
+
+        let mut union_automaton = Automaton::new();
+        let union_start_state = union_automaton.add_state();
+        let union_end_state = union_automaton.add_state();
+
+        for alternative in [self, other] {
+            let alternative_start = alternative.start_state;
+            let alternative_end = *alternative.accepting_states.iter().next().unwrap();
+            let offset = union_automaton.states;
+            union_automaton.add_states_and_transitions(alternative);
+
+            union_automaton.add_transition(union_start_state, alternative_start + offset, None);
+            union_automaton.add_transition(alternative_end + offset, union_end_state, None);
+        }
+
+        union_automaton.set_start_state(union_start_state);
+        union_automaton.clear_accepting();
+        union_automaton.set_accepting(union_end_state, true);
+        union_automaton
+    }
+
+    /// Builds an automaton simulating a regular expression like ```a*```,
+    /// ```a+``` or ```a?```: a new start/end pair wrapping `self`, connected
+    /// by the epsilon edges appropriate for `kind`.
+    pub fn closure(self, kind: ClosureKind) -> Automaton {
+        assert_eq!(self.accepting_states.len(), 1);
+
+        let mut closure_automaton = Automaton::new();
+        let closure_start_state = closure_automaton.add_state();
+        let closure_end_state = closure_automaton.add_state();
+
+        let offset = closure_automaton.states;
+        let inner_start = self.start_state;
+        let inner_end = *self.accepting_states.iter().next().unwrap();
+        closure_automaton.add_states_and_transitions(self);
+
+        closure_automaton.add_transition(closure_start_state, inner_start + offset, None);
+        closure_automaton.add_transition(inner_end + offset, closure_end_state, None);
+
+        match kind {
+            ClosureKind::OneOrMore => {
+                closure_automaton.add_transition(closure_end_state, closure_start_state, None);
+            }
+            ClosureKind::ZeroOrMore => {
+                closure_automaton.add_transition(closure_start_state, closure_end_state, None);
+                closure_automaton.add_transition(closure_end_state, closure_start_state, None);
+            }
+            ClosureKind::ZeroOrOne => {
+                closure_automaton.add_transition(closure_start_state, closure_end_state, None);
+            }
+        }
+
+        closure_automaton.set_start_state(closure_start_state);
+        closure_automaton.clear_accepting();
+        closure_automaton.set_accepting(closure_end_state, true);
+        closure_automaton
+    }
+
+    /// Builds an automaton simulating bounded repetition like ```a{2,4}```,
+    /// by Thompson construction: `min` mandatory copies of `self`
+    /// concatenated together, followed either by `max - min` optional
+    /// copies (a finite `max`) or a single `*`-closure copy (`max` is
+    /// `None`, i.e. unbounded).
+    ///
+    /// Panics if `max` is `Some` and less than `min`; callers are expected to
+    /// reject that case themselves and report it as a structured error.
+    pub fn repeat(self, min: usize, max: Option<usize>) -> Automaton {
+        if let Some(max) = max {
+            assert!(min <= max, "repetition min must not be greater than max");
+        }
+
+        let mut mandatory = (0..min).map(|_| self.clone());
+        let mut result = mandatory.next().unwrap_or_else(Automaton::epsilon);
+        for copy in mandatory {
+            result = result.concat(copy);
+        }
+
+        match max {
+            Some(max) => {
+                for _ in min..max {
+                    result = result.concat(self.clone().closure(ClosureKind::ZeroOrOne));
+                }
+                result
+            }
+            None => result.concat(self.closure(ClosureKind::ZeroOrMore)),
+        }
+    }
+
+    /// Runs `input` through this automaton and reports whether it's
+    /// accepted, by tracking the set of all states reachable via zero or
+    /// more epsilon moves plus the next symbol, one char at a time.
+    pub fn accepts(&self, input: &str) -> bool {
+        let mut current = self.epsilon_closure([self.start_state].into_iter().collect());
+
+        for c in input.chars() {
+            let mut next = HashSet::new();
+            for &state in &current {
+                if let Some(tos) = self.transitions.get(&(state, Some(c))) {
+                    next.extend(tos);
+                }
+            }
+            current = self.epsilon_closure(next);
+        }
+
+        current.iter().any(|state| self.accepting_states.contains(state))
+    }
+
+    fn epsilon_closure(&self, mut states: HashSet<usize>) -> HashSet<usize> {
+        let mut stack: Vec<usize> = states.iter().copied().collect();
+        while let Some(state) = stack.pop() {
+            if let Some(tos) = self.transitions.get(&(state, None)) {
+                for &to in tos {
+                    if states.insert(to) {
+                        stack.push(to);
+                    }
+                }
+            }
+        }
+        states
+    }
+}
+
+impl Default for Automaton {
+    fn default() -> Self {
+        Automaton::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repeat_exact_bound_only_accepts_exactly_n_copies() {
+        let automaton = Automaton::literal('a').repeat(2, Some(2));
+
+        assert!(!automaton.accepts("a"));
+        assert!(automaton.accepts("aa"));
+        assert!(!automaton.accepts("aaa"));
+    }
+
+    #[test]
+    fn repeat_bounded_range_accepts_between_min_and_max() {
+        let automaton = Automaton::literal('a').repeat(2, Some(4));
+
+        assert!(!automaton.accepts(""));
+        assert!(!automaton.accepts("a"));
+        assert!(automaton.accepts("aa"));
+        assert!(automaton.accepts("aaa"));
+        assert!(automaton.accepts("aaaa"));
+        assert!(!automaton.accepts("aaaaa"));
+    }
+
+    #[test]
+    fn repeat_unbounded_max_accepts_at_least_min() {
+        let automaton = Automaton::literal('a').repeat(2, None);
+
+        assert!(!automaton.accepts("a"));
+        assert!(automaton.accepts("aa"));
+        assert!(automaton.accepts("aaaaaa"));
+    }
+
+    #[test]
+    fn repeat_zero_min_accepts_empty_string() {
+        let automaton = Automaton::literal('a').repeat(0, Some(2));
+
+        assert!(automaton.accepts(""));
+        assert!(automaton.accepts("a"));
+        assert!(automaton.accepts("aa"));
+        assert!(!automaton.accepts("aaa"));
+    }
+
+    #[test]
+    fn concat_union_closure_compose_like_a_regular_expression() {
+        // (a|b)c*
+        let automaton = Automaton::literal('a')
+            .union(Automaton::literal('b'))
+            .concat(Automaton::literal('c').closure(ClosureKind::ZeroOrMore));
+
+        assert!(automaton.accepts("a"));
+        assert!(automaton.accepts("b"));
+        assert!(automaton.accepts("acccc"));
+        assert!(!automaton.accepts("c"));
+        assert!(!automaton.accepts(""));
+    }
+}
@@ -0,0 +1,119 @@
+use crate::lexer::Token;
+use std::fmt;
+
+/// Something the parser was looking for at a given position but did not find.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Expected {
+    Token(Token),
+    Atom,
+    EndOfInput,
+}
+
+impl fmt::Display for Expected {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Expected::Token(token) => write!(f, "{}", token),
+            Expected::Atom => write!(f, "an atom"),
+            Expected::EndOfInput => write!(f, "end of input"),
+        }
+    }
+}
+
+/// A single problem found while parsing a regular expression, carrying
+/// enough information to point the user at exactly where it went wrong.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// A token didn't match what the grammar expected at that point.
+    UnexpectedToken {
+        /// Char offset into the input where the error was found. For an
+        /// unclosed `(`, this is the offset of that `(` itself.
+        position: usize,
+        /// The token that was actually found in place of `expected`, or
+        /// `None` if the input ended before something was found.
+        found: Option<Token>,
+        expected: Vec<Expected>,
+    },
+    /// A `{min,max}` repetition had `min > max`.
+    InvalidRepetitionBounds {
+        position: usize,
+        min: usize,
+        max: usize,
+    },
+}
+
+impl ParseError {
+    pub(crate) fn unexpected_token(
+        position: usize,
+        found: Option<Token>,
+        expected: Vec<Expected>,
+    ) -> Self {
+        Self::UnexpectedToken {
+            position,
+            found,
+            expected,
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::UnexpectedToken {
+                position,
+                found,
+                expected,
+            } => {
+                let expected = expected
+                    .iter()
+                    .map(Expected::to_string)
+                    .collect::<Vec<_>>()
+                    .join(" or ");
+                match found {
+                    Some(token) => write!(
+                        f,
+                        "expected {} at position {}, found {}",
+                        expected, position, token
+                    ),
+                    None => write!(
+                        f,
+                        "expected {} at position {}, found end of input",
+                        expected, position
+                    ),
+                }
+            }
+            ParseError::InvalidRepetitionBounds { position, min, max } => write!(
+                f,
+                "invalid repetition `{{{},{}}}` at position {}: min must not be greater than max",
+                min, max, position
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// An error raised while compiling a [`crate::ParseTree`] into an
+/// [`crate::automaton::Automaton`].
+///
+/// Unlike `ParseError`, this doesn't carry a source position: a `ParseTree`
+/// can be built directly (its variants are public) without ever going
+/// through the parser, so there's no input text to point at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AutomatonError {
+    /// A `Repetition` node's bounds had `min > max`.
+    InvalidRepetitionBounds { min: usize, max: usize },
+}
+
+impl fmt::Display for AutomatonError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AutomatonError::InvalidRepetitionBounds { min, max } => write!(
+                f,
+                "invalid repetition {{{},{}}}: min must not be greater than max",
+                min, max
+            ),
+        }
+    }
+}
+
+impl std::error::Error for AutomatonError {}
@@ -0,0 +1,207 @@
+use std::fmt;
+
+/// A single lexical token produced from a regular expression's source text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Token {
+    LParen,
+    RParen,
+    Pipe,
+    Star,
+    Plus,
+    Question,
+    /// A bounded repetition `{n}`, `{n,}` or `{n,m}`. `max` is `None` for
+    /// the unbounded `{n,}` form.
+    Repetition { min: usize, max: Option<usize> },
+    Literal(char),
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Token::LParen => write!(f, "`(`"),
+            Token::RParen => write!(f, "`)`"),
+            Token::Pipe => write!(f, "`|`"),
+            Token::Star => write!(f, "`*`"),
+            Token::Plus => write!(f, "`+`"),
+            Token::Question => write!(f, "`?`"),
+            Token::Repetition { min, max: Some(max) } => write!(f, "`{{{},{}}}`", min, max),
+            Token::Repetition { min, max: None } => write!(f, "`{{{},}}`", min),
+            Token::Literal(c) => write!(f, "`{}`", c),
+        }
+    }
+}
+
+/// A [`Token`] paired with the char offset into the source it was lexed from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpannedToken {
+    pub token: Token,
+    pub position: usize,
+}
+
+/// Turns `input` into a stream of tokens, resolving escape sequences up
+/// front so the parser never has to reason about a bare `\`.
+///
+/// `\*`, `\+`, `\?`, `\(`, `\)` and `\|` escape the corresponding
+/// metacharacter into a `Literal`. `\n` and `\t` become the matching control
+/// char, and `\\` becomes a single literal backslash. A trailing, dangling
+/// `\` (nothing left to escape) is treated as a literal backslash.
+///
+/// A `{` is read as the start of a bounded repetition (`{n}`, `{n,}` or
+/// `{n,m}`) when what follows matches that shape; otherwise it's lexed as a
+/// literal `{`, same as any other non-metacharacter.
+pub fn lex(input: &str) -> Vec<SpannedToken> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::with_capacity(chars.len());
+    let mut i = 0;
+    while i < chars.len() {
+        let position = i;
+        if chars[i] == '{' {
+            if let Some((token, consumed)) = lex_repetition(&chars[i..]) {
+                tokens.push(SpannedToken { token, position });
+                i += consumed;
+                continue;
+            }
+        }
+
+        let token = match chars[i] {
+            '(' => Token::LParen,
+            ')' => Token::RParen,
+            '|' => Token::Pipe,
+            '*' => Token::Star,
+            '+' => Token::Plus,
+            '?' => Token::Question,
+            '\\' => {
+                i += 1;
+                Token::Literal(match chars.get(i) {
+                    Some('n') => '\n',
+                    Some('t') => '\t',
+                    Some(&other) => other,
+                    None => '\\',
+                })
+            }
+            other => Token::Literal(other),
+        };
+        tokens.push(SpannedToken { token, position });
+        i += 1;
+    }
+    tokens
+}
+
+/// Tries to read a `{n}`, `{n,}` or `{n,m}` run starting at `chars[0]`
+/// (which must be `{`). Returns the token and how many chars it consumed,
+/// or `None` if `chars` doesn't start with that shape.
+fn lex_repetition(chars: &[char]) -> Option<(Token, usize)> {
+    let mut i = 1;
+
+    let min_start = i;
+    while chars.get(i).is_some_and(char::is_ascii_digit) {
+        i += 1;
+    }
+    if i == min_start {
+        return None;
+    }
+    let min = chars[min_start..i].iter().collect::<String>().parse().ok()?;
+
+    let max = if chars.get(i) == Some(&',') {
+        i += 1;
+        let max_start = i;
+        while chars.get(i).is_some_and(char::is_ascii_digit) {
+            i += 1;
+        }
+        if i == max_start {
+            None
+        } else {
+            Some(chars[max_start..i].iter().collect::<String>().parse().ok()?)
+        }
+    } else {
+        Some(min)
+    };
+
+    if chars.get(i) != Some(&'}') {
+        return None;
+    }
+    i += 1;
+
+    Some((Token::Repetition { min, max }, i))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokens(input: &str) -> Vec<Token> {
+        lex(input).into_iter().map(|t| t.token).collect()
+    }
+
+    #[test]
+    fn escapes_resolve_metacharacters_to_literals() {
+        assert_eq!(tokens(r"\*\+\?\(\)\|"), vec![
+            Token::Literal('*'),
+            Token::Literal('+'),
+            Token::Literal('?'),
+            Token::Literal('('),
+            Token::Literal(')'),
+            Token::Literal('|'),
+        ]);
+    }
+
+    #[test]
+    fn escapes_resolve_control_chars_and_backslash() {
+        assert_eq!(
+            tokens(r"\n\t\\"),
+            vec![Token::Literal('\n'), Token::Literal('\t'), Token::Literal('\\')]
+        );
+    }
+
+    #[test]
+    fn dangling_backslash_at_end_of_input_is_a_literal_backslash() {
+        assert_eq!(tokens(r"a\"), vec![Token::Literal('a'), Token::Literal('\\')]);
+    }
+
+    #[test]
+    fn bounded_repetition_forms_are_tokenized() {
+        assert_eq!(
+            tokens("a{2}"),
+            vec![
+                Token::Literal('a'),
+                Token::Repetition { min: 2, max: Some(2) }
+            ]
+        );
+        assert_eq!(
+            tokens("a{2,}"),
+            vec![Token::Literal('a'), Token::Repetition { min: 2, max: None }]
+        );
+        assert_eq!(
+            tokens("a{2,4}"),
+            vec![
+                Token::Literal('a'),
+                Token::Repetition { min: 2, max: Some(4) }
+            ]
+        );
+    }
+
+    #[test]
+    fn malformed_brace_run_falls_back_to_literal_braces() {
+        assert_eq!(
+            tokens("a{}"),
+            vec![Token::Literal('a'), Token::Literal('{'), Token::Literal('}')]
+        );
+        assert_eq!(
+            tokens("a{x}"),
+            vec![
+                Token::Literal('a'),
+                Token::Literal('{'),
+                Token::Literal('x'),
+                Token::Literal('}')
+            ]
+        );
+    }
+
+    #[test]
+    fn positions_track_char_offsets_not_token_indices() {
+        let spanned = lex(r"\n(a{2,4}");
+        let positions: Vec<usize> = spanned.iter().map(|t| t.position).collect();
+        // `\n` -> 1 token spanning chars 0-1, then `(` at 2, `a` at 3, `{2,4}` at 4.
+        assert_eq!(positions, vec![0, 2, 3, 4]);
+    }
+}
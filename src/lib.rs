@@ -0,0 +1,8 @@
+pub mod automaton;
+pub mod error;
+pub mod lexer;
+pub mod parse_tree;
+pub mod translator;
+
+pub use error::{AutomatonError, Expected, ParseError};
+pub use parse_tree::ParseTree;
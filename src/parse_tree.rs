@@ -1,4 +1,6 @@
-pub mod parse_tree;
+use crate::automaton::{Automaton, ClosureKind};
+use crate::error::{AutomatonError, Expected, ParseError};
+use crate::lexer::{self, SpannedToken, Token};
 
 #[derive(Debug)]
 pub enum ParseTree {
@@ -19,22 +21,139 @@ pub enum ParseTree {
     Plus {
         inner: Box<ParseTree>,
     },
+    /// Bounded repetition `{n}`, `{n,}` or `{n,m}`. `max` is `None` for the
+    /// unbounded `{n,}` form.
+    Repetition {
+        inner: Box<ParseTree>,
+        min: usize,
+        max: Option<usize>,
+    },
     Atom(char),
     Empty,
 }
 
 impl ParseTree {
-    pub fn from(input: &str) -> ParseTree {
-        let input_mut: Vec<char> = input.chars().collect();
-        let mut iter = input_mut.iter().peekable();
-        ParseTree::build_tree(&mut iter)
+    /// Lexes and parses `input` into a `ParseTree`.
+    ///
+    /// Unlike a single `panic!`-on-first-error parser, this recovers from an
+    /// unbalanced `(` by synthesizing the missing `)` and continuing, so a
+    /// single call can surface every problem in `input` instead of just the
+    /// first one. A tree is always built, even when problems are found along
+    /// the way: `Ok` is returned when `input` was clean, and `Err` carries
+    /// that same recovered tree back out alongside every `ParseError`
+    /// collected, so a caller that wants to use the library on imperfect
+    /// input (e.g. while the user is still typing) isn't forced to throw the
+    /// tree away just because something was recovered from.
+    pub fn from(input: &str) -> Result<ParseTree, (ParseTree, Vec<ParseError>)> {
+        let mut parser = Parser::new(input.chars().count(), lexer::lex(input));
+        let tree = parser.build_tree();
+        parser.expect_end();
+        if parser.errors.is_empty() {
+            Ok(tree)
+        } else {
+            Err((tree, parser.errors))
+        }
     }
 
-    fn build_tree(mut iter: &mut std::iter::Peekable<std::slice::Iter<'_, char>>) -> ParseTree {
-        let tree = ParseTree::build_term(&mut iter);
-        match iter.next() {
-            Some('|') => {
-                let next_term_tree = ParseTree::build_tree(&mut iter);
+    /// Compiles this parse tree into an [`Automaton`] via Thompson
+    /// construction, using the same `concat`/`union`/`closure` combinators
+    /// the `regex_syntax`-based translator uses, so both frontends produce
+    /// NFAs through a single shared code path.
+    ///
+    /// `ParseTree`'s variants are public, so a `Repetition` with `min > max`
+    /// can be built directly without going through [`ParseTree::from`]'s own
+    /// validation. Rather than panic on that, this returns `Err` so the
+    /// invariant can't be broken from outside the parser.
+    pub fn to_automaton(&self) -> Result<Automaton, AutomatonError> {
+        Ok(match self {
+            ParseTree::Or { left, right } => left.to_automaton()?.union(right.to_automaton()?),
+            ParseTree::Concatenation { left, right } => {
+                left.to_automaton()?.concat(right.to_automaton()?)
+            }
+            ParseTree::Star { inner } => inner.to_automaton()?.closure(ClosureKind::ZeroOrMore),
+            ParseTree::Plus { inner } => inner.to_automaton()?.closure(ClosureKind::OneOrMore),
+            ParseTree::Question { inner } => inner.to_automaton()?.closure(ClosureKind::ZeroOrOne),
+            ParseTree::Repetition { inner, min, max } => {
+                if let Some(max) = *max {
+                    if *min > max {
+                        return Err(AutomatonError::InvalidRepetitionBounds { min: *min, max });
+                    }
+                }
+                inner.to_automaton()?.repeat(*min, *max)
+            }
+            ParseTree::Atom(c) => Automaton::literal(*c),
+            ParseTree::Empty => Automaton::epsilon(),
+        })
+    }
+}
+
+/// Consumes a token stream produced by [`lexer::lex`]. `pos` indexes into
+/// `tokens`; use [`Parser::position_of`] to turn such an index back into the
+/// char offset a `ParseError` should report, since a single token (an
+/// escape, a `{n,m}` run) can span more than one source char.
+struct Parser {
+    tokens: Vec<SpannedToken>,
+    pos: usize,
+    /// Char length of the original input, reported as the error position
+    /// for problems found at end-of-input.
+    source_len: usize,
+    errors: Vec<ParseError>,
+}
+
+impl Parser {
+    fn new(source_len: usize, tokens: Vec<SpannedToken>) -> Self {
+        Self {
+            tokens,
+            pos: 0,
+            source_len,
+            errors: Vec::new(),
+        }
+    }
+
+    fn peek(&self) -> Option<Token> {
+        self.tokens.get(self.pos).map(|t| t.token)
+    }
+
+    fn advance(&mut self) -> Option<Token> {
+        let token = self.peek();
+        if token.is_some() {
+            self.pos += 1;
+        }
+        token
+    }
+
+    /// Converts a token index (as tracked by `pos`) into the char offset of
+    /// that token in the original input, falling back to `source_len` for an
+    /// index at or past the end of the token stream.
+    fn position_of(&self, index: usize) -> usize {
+        self.tokens
+            .get(index)
+            .map(|t| t.position)
+            .unwrap_or(self.source_len)
+    }
+
+    /// Reports every token left over after the top-level parse as an
+    /// `UnexpectedToken` error. Without this, a stray `)` or any garbage
+    /// trailing a complete expression (e.g. the `)b` in `a)b`) would be
+    /// silently dropped: `build_term` stops as soon as it sees a `)`, and
+    /// nothing upstream otherwise checks that the whole input was consumed.
+    fn expect_end(&mut self) {
+        while let Some(spanned) = self.tokens.get(self.pos).copied() {
+            self.errors.push(ParseError::unexpected_token(
+                spanned.position,
+                Some(spanned.token),
+                vec![Expected::EndOfInput],
+            ));
+            self.pos += 1;
+        }
+    }
+
+    fn build_tree(&mut self) -> ParseTree {
+        let tree = self.build_term();
+        match self.peek() {
+            Some(Token::Pipe) => {
+                self.advance();
+                let next_term_tree = self.build_tree();
                 ParseTree::Or {
                     left: Box::new(tree),
                     right: Box::new(next_term_tree),
@@ -44,18 +163,13 @@ impl ParseTree {
         }
     }
 
-    fn build_term(mut iter: &mut std::iter::Peekable<std::slice::Iter<'_, char>>) -> ParseTree {
+    fn build_term(&mut self) -> ParseTree {
         let mut factor_tree = ParseTree::Empty;
-        while let Some(c) = iter.peek() {
-            match c {
-                ')' => {
-                    break;
-                }
-                '|' => {
-                    break;
-                }
+        while let Some(token) = self.peek() {
+            match token {
+                Token::RParen | Token::Pipe => break,
                 _ => {
-                    let next_factor_tree = ParseTree::build_factor(&mut iter);
+                    let next_factor_tree = self.build_factor();
                     factor_tree = ParseTree::Concatenation {
                         left: Box::new(factor_tree),
                         right: Box::new(next_factor_tree),
@@ -66,30 +180,216 @@ impl ParseTree {
         factor_tree
     }
 
-    fn build_factor(mut iter: &mut std::iter::Peekable<std::slice::Iter<'_, char>>) -> ParseTree {
-        let mut base_tree = ParseTree::build_base(&mut iter);
-        while let Some('*') = iter.peek() {
-            iter.next();
-            base_tree = ParseTree::Star {
-                inner: Box::new(base_tree),
-            };
+    fn build_factor(&mut self) -> ParseTree {
+        let mut base_tree = self.build_base();
+        loop {
+            match self.peek() {
+                Some(Token::Star) => {
+                    self.advance();
+                    base_tree = ParseTree::Star {
+                        inner: Box::new(base_tree),
+                    };
+                }
+                Some(Token::Plus) => {
+                    self.advance();
+                    base_tree = ParseTree::Plus {
+                        inner: Box::new(base_tree),
+                    };
+                }
+                Some(Token::Question) => {
+                    self.advance();
+                    base_tree = ParseTree::Question {
+                        inner: Box::new(base_tree),
+                    };
+                }
+                Some(Token::Repetition { min, max }) => {
+                    let position = self.position_of(self.pos);
+                    self.advance();
+                    if max.is_some_and(|max| min > max) {
+                        self.errors.push(ParseError::InvalidRepetitionBounds {
+                            position,
+                            min,
+                            max: max.unwrap(),
+                        });
+                        continue;
+                    }
+                    base_tree = ParseTree::Repetition {
+                        inner: Box::new(base_tree),
+                        min,
+                        max,
+                    };
+                }
+                _ => break,
+            }
         }
         base_tree
     }
 
-    fn build_base(iter: &mut std::iter::Peekable<std::slice::Iter<'_, char>>) -> ParseTree {
-        match iter.next() {
-            Some('(') => {
-                let tree = ParseTree::build_tree(iter);
-                if let Some(')') = iter.next() {
-                    tree
-                } else {
-                    panic!("Invalid regular expression");
+    fn build_base(&mut self) -> ParseTree {
+        let start = self.position_of(self.pos);
+        match self.advance() {
+            Some(Token::LParen) => {
+                let tree = self.build_tree();
+                match self.peek() {
+                    Some(Token::RParen) => {
+                        self.advance();
+                    }
+                    found => {
+                        // Unbalanced `(`: synthesize the missing `)` and keep
+                        // going instead of aborting the whole parse. Point at
+                        // the `(` itself rather than wherever parsing ran out
+                        // looking for its match, since that's what the user
+                        // needs to fix.
+                        self.errors.push(ParseError::unexpected_token(
+                            start,
+                            found,
+                            vec![Expected::Token(Token::RParen)],
+                        ));
+                    }
                 }
+                tree
+            }
+            Some(Token::Literal(c)) => ParseTree::Atom(c),
+            found @ Some(_) => {
+                self.errors
+                    .push(ParseError::unexpected_token(start, found, vec![Expected::Atom]));
+                ParseTree::Empty
+            }
+            None => {
+                self.errors
+                    .push(ParseError::unexpected_token(start, None, vec![Expected::Atom]));
+                ParseTree::Empty
             }
-            Some('\\') => ParseTree::Atom('\\'),
-            Some(c) => ParseTree::Atom(*c),
-            None => panic!("Invalid regular expression"),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unbalanced_open_paren_recovers_with_an_error_at_the_paren() {
+        let (_, errors) = ParseTree::from("(a").unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(
+            errors[0],
+            ParseError::UnexpectedToken {
+                expected: _,
+                found: None,
+                position: 0,
+            }
+        ));
+    }
+
+    #[test]
+    fn stray_close_paren_is_reported_not_dropped() {
+        let (_, errors) = ParseTree::from(")").unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(
+            errors[0],
+            ParseError::UnexpectedToken {
+                found: Some(Token::RParen),
+                position: 0,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn trailing_garbage_after_a_valid_expression_is_reported() {
+        let (_, errors) = ParseTree::from("a)b").unwrap_err();
+        assert_eq!(errors.len(), 2);
+        assert!(matches!(
+            errors[0],
+            ParseError::UnexpectedToken {
+                found: Some(Token::RParen),
+                position: 1,
+                ..
+            }
+        ));
+        assert!(matches!(
+            errors[1],
+            ParseError::UnexpectedToken {
+                found: Some(Token::Literal('b')),
+                position: 2,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn valid_input_parses_without_errors() {
+        assert!(ParseTree::from("a(b|c)*").is_ok());
+    }
+
+    #[test]
+    fn error_position_is_a_char_offset_not_a_token_index() {
+        // `\n\n\n(a`: 3 one-token-per-char-pair escapes (chars 0-1, 2-3, 4-5),
+        // then an unbalanced `(` at char 6 followed by `a` at char 7. The
+        // error should point at the `(` (char 6), not at its token index (3)
+        // and not at the end of input (char 8).
+        let (_, errors) = ParseTree::from("\\n\\n\\n(a").unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(
+            errors[0],
+            ParseError::UnexpectedToken {
+                found: None,
+                position: 6,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn invalid_repetition_bounds_are_reported_not_panicked() {
+        let (_, errors) = ParseTree::from("a{4,2}").unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(
+            errors[0],
+            ParseError::InvalidRepetitionBounds {
+                min: 4,
+                max: 2,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn recovered_tree_is_still_usable_after_an_unbalanced_paren() {
+        // `(a` recovers by synthesizing the missing `)`, so the tree it
+        // builds is a perfectly good `a` even though the input had a problem.
+        let (tree, errors) = ParseTree::from("(a").unwrap_err();
+        assert_eq!(errors.len(), 1);
+        let automaton = tree.to_automaton().unwrap();
+        assert!(automaton.accepts("a"));
+        assert!(!automaton.accepts(""));
+    }
+
+    #[test]
+    fn to_automaton_accepts_bounded_repetition() {
+        let tree = ParseTree::from("a{2,3}").unwrap();
+        let automaton = tree.to_automaton().unwrap();
+
+        assert!(!automaton.accepts("a"));
+        assert!(automaton.accepts("aa"));
+        assert!(automaton.accepts("aaa"));
+        assert!(!automaton.accepts("aaaa"));
+    }
+
+    #[test]
+    fn to_automaton_rejects_a_directly_constructed_invalid_repetition() {
+        // `ParseTree::from` never produces a `Repetition` with `min > max`,
+        // but nothing stops a caller from building one directly.
+        let tree = ParseTree::Repetition {
+            inner: Box::new(ParseTree::Atom('a')),
+            min: 4,
+            max: Some(2),
+        };
+
+        assert!(matches!(
+            tree.to_automaton(),
+            Err(AutomatonError::InvalidRepetitionBounds { min: 4, max: 2 })
+        ));
+    }
+}
@@ -1,4 +1,4 @@
-use crate::automaton::Automaton;
+use crate::automaton::{Automaton, ClosureKind};
 use regex_syntax::ast::parse::Parser;
 use regex_syntax::ast::{
     Alternation, Ast, Class, ClassSet, ClassSetItem, ClassSetRange, Concat, Error, Repetition,
@@ -87,139 +87,59 @@ fn build_class_set_range(class_set_range: &ClassSetRange) -> TranslatorResult {
 }
 
 /// Builds an automaton simulating a regular expression like ```abc```
-/// by appending each symbol to the end state of the previous symbol, a -> b -> _c_
+/// by folding each symbol's automaton onto the previous one with `concat`.
 fn build_concatenation(concat_ast: &Concat) -> TranslatorResult {
-    let mut concat_automaton = Automaton::new();
-    let concat_start_state = concat_automaton.add_state();
-    concat_automaton.set_start_state(concat_start_state);
-
-    let mut concat_end_state = concat_start_state;
-
-    for append_ast in &concat_ast.asts {
-        let append_automaton = build_tree(append_ast)?;
-        assert_eq!(append_automaton.accepting_states.len(), 1);
-        let append_start_state = append_automaton.start_state;
-        let append_end_state = *append_automaton.accepting_states.iter().next().unwrap();
-        let concat_append_offset = concat_automaton.states;
-        concat_automaton.add_states_and_transitions(append_automaton);
-
-        // Add transition from previous append_automaton's end state to current append_automaton's start state
-        concat_automaton.add_transition(
-            concat_end_state,
-            append_start_state + concat_append_offset,
-            None,
-        );
-
-        // Change end state to be the current append_automaton's end state
-        concat_end_state = append_end_state + concat_append_offset;
-        concat_automaton.clear_accepting();
-        concat_automaton.set_accepting(concat_end_state, true);
-    }
-
-    Ok(concat_automaton)
+    let mut asts = concat_ast.asts.iter();
+    let first = match asts.next() {
+        Some(ast) => build_tree(ast)?,
+        None => return Ok(Automaton::epsilon()),
+    };
+
+    asts.try_fold(first, |concat_automaton, append_ast| {
+        Ok(concat_automaton.concat(build_tree(append_ast)?))
+    })
 }
 
-/// Builds an automaton simulating a regular expression like ```a?```, ```a+``` or ```a*```
-/// For ```?```, create two states with the repeating automaton between them, and add an epsilon
-/// transition from the starting state to the end (accepting) state.
-/// For ```+```, create two states with the repeating automaton between them, and add an epsilon
-/// transition from the end (accepting) state to the starting state.
-/// For ```*```, create two states with the repeating automaton between them, and add an epsilon
-/// transition from the starting state to the end (accepting) state, and an epsilon transition from
-/// the end (accepting) state to the starting state.
+/// Builds an automaton simulating a regular expression like ```a?```, ```a+```,
+/// ```a*``` or bounded repetition like ```a{2,4}```, by wrapping the inner
+/// automaton in the epsilon closure appropriate for the repetition kind (see
+/// `Automaton::closure`), or by expanding it via `Automaton::repeat` for the
+/// `{min,max}` form.
 fn build_repetition(repetition_ast: &Repetition) -> TranslatorResult {
-    use regex_syntax::ast::RepetitionKind;
-
-    let mut repetition_automaton = Automaton::new();
-    let repetition_start_state = repetition_automaton.add_state();
-    let repetition_end_state = repetition_automaton.add_state();
-    let repetition_to_inner_offset = repetition_automaton.states;
+    use regex_syntax::ast::{RepetitionKind, RepetitionRange};
 
     let inner_automaton = build_tree(&repetition_ast.ast)?;
-    assert_eq!(inner_automaton.accepting_states.len(), 1);
-    let inner_automaton_start_state = inner_automaton.start_state;
-    let inner_automaton_end_state = *inner_automaton.accepting_states.iter().next().unwrap();
-    repetition_automaton.add_states_and_transitions(inner_automaton);
-
-    // Add transition from repetition_automaton's start state to inner_automaton's start state
-    repetition_automaton.add_transition(
-        repetition_start_state,
-        inner_automaton_start_state + repetition_to_inner_offset,
-        None,
-    );
-
-    // Add transition from inner_automaton's end state to repetition_automaton's end state
-    repetition_automaton.add_transition(
-        inner_automaton_end_state + repetition_to_inner_offset,
-        repetition_end_state,
-        None,
-    );
 
     match &repetition_ast.op.kind {
-        RepetitionKind::OneOrMore => {
-            // Add transition from repetition_automaton's end state to repetition_automaton's start state
-            repetition_automaton.add_transition(repetition_end_state, repetition_start_state, None);
-        }
-        RepetitionKind::ZeroOrMore => {
-            // Add transition from repetition_automaton's start state to repetition_automaton's end state (for Zero)
-            repetition_automaton.add_transition(repetition_start_state, repetition_end_state, None);
-            // Add transition from repetition_automaton's end state to repetition_automaton's start state
-            repetition_automaton.add_transition(repetition_end_state, repetition_start_state, None);
-        }
-        RepetitionKind::ZeroOrOne => {
-            // Add transition from repetition_automaton's start state to repetition_automaton's end state
-            repetition_automaton.add_transition(repetition_start_state, repetition_end_state, None);
-        }
-        unsupported => {
-            panic!("{:?} is not supported yet", unsupported);
+        RepetitionKind::OneOrMore => Ok(inner_automaton.closure(ClosureKind::OneOrMore)),
+        RepetitionKind::ZeroOrMore => Ok(inner_automaton.closure(ClosureKind::ZeroOrMore)),
+        RepetitionKind::ZeroOrOne => Ok(inner_automaton.closure(ClosureKind::ZeroOrOne)),
+        RepetitionKind::Range(range) => {
+            // `Parser::parse` itself rejects a `{n,m}` with `n > m` (as
+            // `ErrorKind::RepetitionCountInvalid`) before ever building this
+            // `Ast::Repetition`, so `min <= max` always holds here.
+            let (min, max) = match range {
+                RepetitionRange::Exactly(n) => (*n, Some(*n)),
+                RepetitionRange::AtLeast(n) => (*n, None),
+                RepetitionRange::Bounded(min, max) => (*min, Some(*max)),
+            };
+            Ok(inner_automaton.repeat(min as usize, max.map(|max| max as usize)))
         }
     }
-
-    repetition_automaton.set_start_state(repetition_start_state);
-    repetition_automaton.clear_accepting();
-    repetition_automaton.set_accepting(repetition_end_state, true);
-
-    Ok(repetition_automaton)
 }
 
+/// Builds an automaton simulating a regular expression like ```a|b```
+/// by folding each alternative's automaton together with `union`.
 fn build_alternation(alternation_ast: &Alternation) -> TranslatorResult {
-    let mut alternation_automaton = Automaton::new();
-    let alternation_automaton_start_state = alternation_automaton.add_state();
-    let alternation_automaton_end_state = alternation_automaton.add_state();
-
-    for alternative_ast in &alternation_ast.asts {
-        let alternative_automaton = build_tree(alternative_ast)?;
-        assert_eq!(alternative_automaton.accepting_states.len(), 1);
-
-        let alternative_automaton_start_state = alternative_automaton.start_state;
-        let alternative_automaton_end_state = *alternative_automaton
-            .accepting_states
-            .iter()
-            .next()
-            .unwrap();
-        let alternation_to_alternative_offset = alternation_automaton.states;
-        alternation_automaton.add_states_and_transitions(alternative_automaton);
-
-        // Add transition from alternation_automaton's start state to alternative_automaton's start state
-        alternation_automaton.add_transition(
-            alternation_automaton_start_state,
-            alternative_automaton_start_state + alternation_to_alternative_offset,
-            None,
-        );
-
-        // Add transition from alternative_automaton's end state to alternation_automaton's end state
-        alternation_automaton.add_transition(
-            alternative_automaton_end_state + alternation_to_alternative_offset,
-            alternation_automaton_end_state,
-            None,
-        );
-    }
-
-    alternation_automaton.set_start_state(alternation_automaton_start_state);
-    alternation_automaton.clear_accepting();
-    alternation_automaton.set_accepting(alternation_automaton_end_state, true);
-
-    Ok(alternation_automaton)
+    let mut asts = alternation_ast.asts.iter();
+    let first = build_tree(
+        asts.next()
+            .expect("an alternation always has at least one alternative"),
+    )?;
+
+    asts.try_fold(first, |alternation_automaton, alternative_ast| {
+        Ok(alternation_automaton.union(build_tree(alternative_ast)?))
+    })
 }
 
 fn build_literal(atoms: HashSet<char>) -> TranslatorResult {